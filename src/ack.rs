@@ -0,0 +1,119 @@
+//! Parsing of HL7 acknowledgment (ACK) messages.
+//!
+//! An HL7 response carries its outcome in the `MSA` segment: `MSA-1` is the
+//! acknowledgment code (`AA`/`CA` accept, `AE`/`CE` application error, `AR`/`CR` reject)
+//! and `MSA-3` is an optional free-text message. The field separator used to split the
+//! segment isn't fixed — it's whatever character the sender declared in `MSH-1`.
+
+/// The three acknowledgment outcomes defined by the HL7 MSA segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AckCode {
+    Accept,
+    ApplicationError,
+    Reject,
+}
+
+/// A parsed acknowledgment extracted from an HL7 response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Acknowledgment {
+    pub code: AckCode,
+    pub raw_code: String,
+    pub text_message: Option<String>,
+}
+
+impl Acknowledgment {
+    pub fn is_accepted(&self) -> bool {
+        matches!(self.code, AckCode::Accept)
+    }
+}
+
+/// Locates the `MSA` segment in `response`, determines the field separator from `MSH-1`,
+/// and interprets `MSA-1`. Returns `None` if no `MSH` or `MSA` segment is present, or if
+/// `MSA-1` isn't one of the six known acknowledgment codes.
+pub fn parse_acknowledgment(response: &str) -> Option<Acknowledgment> {
+    let field_separator = msh_field_separator(response)?;
+    let msa_segment = response.split('\r').find(|segment| segment.starts_with("MSA"))?;
+
+    let fields: Vec<&str> = msa_segment.split(field_separator).collect();
+    let raw_code = fields.get(1)?.trim().to_string();
+    let text_message = fields
+        .get(3)
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+
+    let code = match raw_code.as_str() {
+        "AA" | "CA" => AckCode::Accept,
+        "AE" | "CE" => AckCode::ApplicationError,
+        "AR" | "CR" => AckCode::Reject,
+        _ => return None,
+    };
+
+    Some(Acknowledgment {
+        code,
+        raw_code,
+        text_message,
+    })
+}
+
+/// `MSH-1` is the single character immediately following the `MSH` segment name, i.e. the
+/// field separator itself (normally `|`).
+fn msh_field_separator(response: &str) -> Option<char> {
+    let msh_segment = response.split('\r').find(|segment| segment.starts_with("MSH"))?;
+    msh_segment.chars().nth(3)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_accept_code_and_text_message() {
+        let response = "MSH|^~\\&|Recv|Fac|Send|Fac|20240101||ACK|MSG1|P|2.5\rMSA|AA|MSG123|All good";
+        let ack = parse_acknowledgment(response).unwrap();
+        assert_eq!(ack.code, AckCode::Accept);
+        assert!(ack.is_accepted());
+        assert_eq!(ack.raw_code, "AA");
+        assert_eq!(ack.text_message.as_deref(), Some("All good"));
+    }
+
+    #[test]
+    fn parses_reject_code_without_text_message() {
+        let response = "MSH|^~\\&|Recv|Fac|Send|Fac|20240101||ACK|MSG1|P|2.5\rMSA|AR|MSG123";
+        let ack = parse_acknowledgment(response).unwrap();
+        assert_eq!(ack.code, AckCode::Reject);
+        assert!(!ack.is_accepted());
+        assert_eq!(ack.text_message, None);
+    }
+
+    #[test]
+    fn parses_application_error_code() {
+        let response = "MSH|^~\\&|Recv|Fac|Send|Fac|20240101||ACK|MSG1|P|2.5\rMSA|AE|MSG123|Bad field";
+        let ack = parse_acknowledgment(response).unwrap();
+        assert_eq!(ack.code, AckCode::ApplicationError);
+    }
+
+    #[test]
+    fn honors_non_default_field_separator() {
+        let response = "MSH$^~\\&$Recv$Fac$Send$Fac$20240101$$ACK$MSG1$P$2.5\rMSA$CA$MSG123$Accepted";
+        let ack = parse_acknowledgment(response).unwrap();
+        assert_eq!(ack.code, AckCode::Accept);
+        assert_eq!(ack.text_message.as_deref(), Some("Accepted"));
+    }
+
+    #[test]
+    fn returns_none_without_msh_segment() {
+        assert_eq!(parse_acknowledgment("MSA|AA|MSG123"), None);
+    }
+
+    #[test]
+    fn returns_none_without_msa_segment() {
+        let response = "MSH|^~\\&|Recv|Fac|Send|Fac|20240101||ACK|MSG1|P|2.5";
+        assert_eq!(parse_acknowledgment(response), None);
+    }
+
+    #[test]
+    fn returns_none_for_unknown_code() {
+        let response = "MSH|^~\\&|Recv|Fac|Send|Fac|20240101||ACK|MSG1|P|2.5\rMSA|XX|MSG123";
+        assert_eq!(parse_acknowledgment(response), None);
+    }
+}
@@ -0,0 +1,353 @@
+//! Async, connection-pooled sending for high-throughput delivery.
+//!
+//! The synchronous paths in `main.rs` open one connection per invocation (or, in
+//! `--batch` mode, one connection per file). Neither scales to feeding a directory of
+//! thousands of messages quickly. This module spins up `--concurrency` Tokio workers,
+//! each holding its own kept-alive `TcpStream`, pulling messages from a shared queue until
+//! it's drained. Framing and ACK parsing are the same [`crate::mllp`] and [`crate::ack`]
+//! logic the sync path uses, so behavior stays identical between the two; only the I/O is
+//! async. Gated behind the `async` feature so the default build stays dependency-light.
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+
+use crate::ack;
+use crate::error::{classify_read_error, SendError};
+use crate::mllp::{frame_message, read_mllp_frame_async};
+use crate::resolve::{resolve_ordered, AddressPreference};
+
+const READ_BUF_SIZE: usize = 4096;
+
+/// Outcome totals for a bulk send run, printed as the final summary line.
+///
+/// `rejected` only counts messages the receiver itself didn't accept (MSA-1 wasn't
+/// `AA`/`CA`); `connection_errors` counts everything connection-level instead — a worker's
+/// initial connect failing, or a write/read failing against an already-established stream.
+/// `undelivered` is the gap between the messages handed to [`send_bulk`] and `sent`: it's
+/// nonzero only when every worker failed to connect at all, leaving messages in the queue
+/// that no worker ever got to dequeue.
+#[derive(Debug, Default)]
+pub struct BulkSummary {
+    pub sent: usize,
+    pub accepted: usize,
+    pub rejected: usize,
+    pub timed_out: usize,
+    pub connection_errors: usize,
+    pub undelivered: usize,
+    pub elapsed: Duration,
+}
+
+impl BulkSummary {
+    pub fn messages_per_sec(&self) -> f64 {
+        if self.elapsed.is_zero() {
+            0.0
+        } else {
+            self.sent as f64 / self.elapsed.as_secs_f64()
+        }
+    }
+}
+
+/// Expands `paths` into a flat list of message files, in the order `paths` are given:
+/// directories are listed non-recursively and their entries included sorted by path,
+/// plain files are passed through as-is.
+pub fn collect_message_files(paths: &[PathBuf]) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            files.extend(list_dir_files(path)?);
+        } else {
+            files.push(path.clone());
+        }
+    }
+    Ok(files)
+}
+
+fn list_dir_files(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect();
+    entries.sort();
+    Ok(entries)
+}
+
+/// Sends every message in `messages` to `host:port` using up to `concurrency` concurrent,
+/// kept-alive connections, applying `connect_timeout` to each connection attempt and
+/// `timeout` to each write and read, and returns
+/// aggregate counts rather than per-message results since a bulk run is meant to be
+/// summarized, not enumerated.
+pub async fn send_bulk(
+    host: String,
+    port: u16,
+    messages: Vec<String>,
+    concurrency: usize,
+    timeout: Duration,
+    connect_timeout: Duration,
+    prefer: Option<AddressPreference>,
+) -> BulkSummary {
+    let total = messages.len();
+    let queue = Arc::new(Mutex::new(VecDeque::from(messages)));
+    let sent = Arc::new(AtomicUsize::new(0));
+    let accepted = Arc::new(AtomicUsize::new(0));
+    let rejected = Arc::new(AtomicUsize::new(0));
+    let timed_out = Arc::new(AtomicUsize::new(0));
+    let connection_errors = Arc::new(AtomicUsize::new(0));
+
+    let started = Instant::now();
+    let workers: Vec<_> = (0..concurrency.max(1))
+        .map(|_| {
+            tokio::spawn(worker_loop(
+                host.clone(),
+                port,
+                timeout,
+                connect_timeout,
+                prefer,
+                Arc::clone(&queue),
+                Arc::clone(&sent),
+                Arc::clone(&accepted),
+                Arc::clone(&rejected),
+                Arc::clone(&timed_out),
+                Arc::clone(&connection_errors),
+            ))
+        })
+        .collect();
+
+    for worker in workers {
+        let _ = worker.await;
+    }
+
+    let sent = sent.load(Ordering::Relaxed);
+    BulkSummary {
+        sent,
+        accepted: accepted.load(Ordering::Relaxed),
+        rejected: rejected.load(Ordering::Relaxed),
+        timed_out: timed_out.load(Ordering::Relaxed),
+        connection_errors: connection_errors.load(Ordering::Relaxed),
+        undelivered: total.saturating_sub(sent),
+        elapsed: started.elapsed(),
+    }
+}
+
+/// Connects once, then drains the shared queue one message at a time over that single
+/// connection until it's empty. A failed send is counted and the worker moves on to the
+/// next message over the same connection; it does not reconnect. A worker that can't even
+/// establish its initial connection counts itself into `connection_errors` rather than
+/// silently returning, so a host that every worker fails to reach is reflected in the
+/// summary instead of being indistinguishable from an empty queue.
+#[allow(clippy::too_many_arguments)]
+async fn worker_loop(
+    host: String,
+    port: u16,
+    timeout: Duration,
+    connect_timeout: Duration,
+    prefer: Option<AddressPreference>,
+    queue: Arc<Mutex<VecDeque<String>>>,
+    sent: Arc<AtomicUsize>,
+    accepted: Arc<AtomicUsize>,
+    rejected: Arc<AtomicUsize>,
+    timed_out: Arc<AtomicUsize>,
+    connection_errors: Arc<AtomicUsize>,
+) {
+    let mut stream = match connect_with_failover(&host, port, prefer, connect_timeout).await {
+        Ok(stream) => stream,
+        Err(_) => {
+            // This worker never sends anything; the remaining queue is simply picked up
+            // by whichever other workers are still healthy. Still record the failure so an
+            // all-workers-unreachable run doesn't read as a clean, empty-queue success.
+            connection_errors.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+    };
+
+    loop {
+        let message = {
+            let mut queue = queue.lock().await;
+            queue.pop_front()
+        };
+        let Some(message) = message else {
+            return;
+        };
+
+        sent.fetch_add(1, Ordering::Relaxed);
+        match send_and_read_ack(&mut stream, &message, timeout).await {
+            Ok(response) => match ack::parse_acknowledgment(&response) {
+                Some(acknowledgment) if acknowledgment.is_accepted() => {
+                    accepted.fetch_add(1, Ordering::Relaxed);
+                }
+                _ => {
+                    rejected.fetch_add(1, Ordering::Relaxed);
+                }
+            },
+            Err(SendError::Timeout(_)) => {
+                timed_out.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(_) => {
+                // Write/read/framing failures against the stream itself, not a receiver
+                // NAK — keep these out of `rejected` so it stays a pure "not accepted by
+                // the receiver" count.
+                connection_errors.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+/// Async counterpart of [`crate::resolve::connect_with_failover`]: resolves `host:port` to
+/// every candidate address (ordered per `prefer`) and tries each in turn, within
+/// `connect_timeout`, until one succeeds.
+async fn connect_with_failover(
+    host: &str,
+    port: u16,
+    prefer: Option<AddressPreference>,
+    connect_timeout: Duration,
+) -> Result<TcpStream, SendError> {
+    let addrs = resolve_ordered(host, port, prefer).map_err(|source| SendError::Connect {
+        host: host.to_string(),
+        port,
+        source,
+    })?;
+
+    let mut last_error = None;
+    for addr in addrs {
+        match tokio::time::timeout(connect_timeout, TcpStream::connect(addr)).await {
+            Ok(Ok(stream)) => return Ok(stream),
+            Ok(Err(e)) => last_error = Some(e),
+            Err(_) => last_error = Some(connect_timeout_error()),
+        }
+    }
+
+    Err(SendError::Connect {
+        host: host.to_string(),
+        port,
+        source: last_error.expect("addrs is non-empty, so the loop above ran at least once"),
+    })
+}
+
+/// Frames `message` as MLLP, writes it to `stream`, and reads back exactly one ACK frame,
+/// mirroring [`crate::send_and_read_ack`] but over an async stream with an explicit
+/// per-call timeout in place of `set_read_timeout`/`set_write_timeout`.
+async fn send_and_read_ack(stream: &mut TcpStream, message: &str, timeout: Duration) -> Result<String, SendError> {
+    tokio::time::timeout(timeout, stream.write_all(&frame_message(message)))
+        .await
+        .map_err(|_| SendError::Timeout(write_timeout_error()))?
+        .map_err(SendError::Write)?;
+
+    let response = tokio::time::timeout(timeout, read_mllp_frame_async(stream, READ_BUF_SIZE))
+        .await
+        .map_err(|_| SendError::Timeout(read_timeout_error()))?
+        .map_err(classify_read_error)?;
+
+    String::from_utf8(response).map_err(SendError::Encoding)
+}
+
+fn connect_timeout_error() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::TimedOut, "connect timed out")
+}
+
+fn write_timeout_error() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::TimedOut, "write timed out")
+}
+
+fn read_timeout_error() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::TimedOut, "read timed out")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write as _;
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn collect_message_files_expands_directories_and_passes_through_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::File::create(dir.path().join("b.hl7")).unwrap().write_all(b"B").unwrap();
+        std::fs::File::create(dir.path().join("a.hl7")).unwrap().write_all(b"A").unwrap();
+
+        let standalone = tempfile::NamedTempFile::new().unwrap();
+
+        let files = collect_message_files(&[dir.path().to_path_buf(), standalone.path().to_path_buf()]).unwrap();
+
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[0], dir.path().join("a.hl7"));
+        assert_eq!(files[1], dir.path().join("b.hl7"));
+        assert_eq!(files[2], standalone.path());
+    }
+
+    #[tokio::test]
+    async fn send_bulk_delivers_every_message_and_tallies_acceptance() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buffer = vec![0u8; READ_BUF_SIZE];
+                    loop {
+                        let n = match stream.read(&mut buffer).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => n,
+                        };
+                        let _ = &buffer[..n];
+                        let ack = "MSH|^~\\&|Recv|Fac|Send|Fac|20240101||ACK|MSG1|P|2.5\rMSA|AA|MSG1";
+                        if stream.write_all(&frame_message(ack)).await.is_err() {
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+
+        let messages: Vec<String> = (0..10).map(|i| format!("MSH|^~\\&|A|B|C|D|20240101||ORU|{i}|P|2.5")).collect();
+        let summary = send_bulk(
+            addr.ip().to_string(),
+            addr.port(),
+            messages,
+            3,
+            Duration::from_secs(5),
+            Duration::from_secs(5),
+            None,
+        )
+        .await;
+
+        assert_eq!(summary.sent, 10);
+        assert_eq!(summary.accepted, 10);
+        assert_eq!(summary.rejected, 0);
+        assert_eq!(summary.timed_out, 0);
+        assert_eq!(summary.connection_errors, 0);
+        assert_eq!(summary.undelivered, 0);
+    }
+
+    #[tokio::test]
+    async fn send_bulk_reports_connection_errors_for_unreachable_host() {
+        // Bind then drop the listener so the port is very likely to refuse connections
+        // for the remainder of the test, without depending on a specific reserved port.
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let messages: Vec<String> = (0..5).map(|i| format!("MSH|^~\\&|A|B|C|D|20240101||ORU|{i}|P|2.5")).collect();
+        let summary = send_bulk(
+            addr.ip().to_string(),
+            addr.port(),
+            messages,
+            3,
+            Duration::from_secs(1),
+            Duration::from_secs(1),
+            None,
+        )
+        .await;
+
+        assert_eq!(summary.sent, 0);
+        assert_eq!(summary.undelivered, 5);
+        assert!(summary.connection_errors > 0, "expected every worker's connect attempt to fail");
+    }
+}
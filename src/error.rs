@@ -0,0 +1,99 @@
+//! Structured error types for the sender, with a stable process exit code per variant so
+//! scripts can distinguish failure modes without matching on stderr text.
+
+use std::io;
+use thiserror::Error;
+
+/// Everything that can go wrong while framing, sending, or reading the ACK for a single
+/// HL7 message.
+#[derive(Debug, Error)]
+pub enum SendError {
+    #[error("failed to connect to {host}:{port}: {source}")]
+    Connect {
+        host: String,
+        port: u16,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("timed out waiting for a response: {0}")]
+    Timeout(#[source] io::Error),
+
+    #[error("failed to write message: {0}")]
+    Write(#[source] io::Error),
+
+    #[error("connection closed before a complete MLLP frame was received")]
+    IncompleteFrame,
+
+    #[error("message was not accepted (MSA-1: {0})")]
+    Nak(String),
+
+    #[error("response was not valid UTF-8: {0}")]
+    Encoding(#[from] std::string::FromUtf8Error),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+}
+
+impl SendError {
+    /// Maps this variant to a stable process exit code.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            SendError::Connect { .. } => 2,
+            SendError::Timeout(_) => 3,
+            SendError::Write(_) => 4,
+            SendError::IncompleteFrame => 5,
+            SendError::Nak(_) => 6,
+            SendError::Encoding(_) => 7,
+            SendError::Io(_) => 1,
+        }
+    }
+}
+
+/// Classifies an `io::Error` raised while reading an MLLP frame as the appropriate
+/// `SendError` variant.
+pub fn classify_read_error(error: io::Error) -> SendError {
+    match error.kind() {
+        io::ErrorKind::UnexpectedEof => SendError::IncompleteFrame,
+        io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => SendError::Timeout(error),
+        _ => SendError::Io(error),
+    }
+}
+
+/// Top-level error for a single invocation of the CLI, covering file and batch-level
+/// failures in addition to `SendError`.
+#[derive(Debug, Error)]
+pub enum AppError {
+    #[error("Failed to open message file: {0}")]
+    OpenMessageFile(#[source] io::Error),
+
+    #[error("Failed to read message file: {0}")]
+    ReadMessageFile(#[source] io::Error),
+
+    #[error("Batch file did not contain any messages")]
+    EmptyBatch,
+
+    #[error(transparent)]
+    Send(#[from] SendError),
+
+    #[error("{0} of {1} messages failed")]
+    BatchFailures(usize, usize),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    #[cfg(not(feature = "async"))]
+    #[error("--bulk requires the crate to be built with the `async` feature enabled")]
+    AsyncFeatureDisabled,
+}
+
+impl AppError {
+    /// Maps this error to a stable process exit code, delegating to the wrapped
+    /// `SendError` when applicable.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            AppError::Send(e) => e.exit_code(),
+            _ => 1,
+        }
+    }
+}
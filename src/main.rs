@@ -1,109 +1,373 @@
 #[cfg(test)]
 mod tests;
+mod ack;
+#[cfg(feature = "async")]
+mod bulk;
+mod error;
+mod mllp;
+mod resolve;
+#[cfg(unix)]
+mod transport;
+
 use std::fs::File;
-use std::io::{self, Read, Write};
-use std::net::TcpStream;
+use std::io::{Read, Write};
+use std::path::PathBuf;
 use std::time::Duration;
 use clap::Parser;
 
+use error::{classify_read_error, AppError, SendError};
+use mllp::{frame_message, read_mllp_frame, split_batch_input};
+use resolve::{connect_with_failover, AddressPreference};
+#[cfg(unix)]
+use transport::connect_unix_stream;
+
 const DEFAULT_TIMEOUT: u64 = 30;
+const DEFAULT_CONNECT_TIMEOUT: u64 = 10;
 const BUFFER_SIZE: usize = 4096;
 
 struct Config {
     timeout: Duration,
+    connect_timeout: Duration,
+    prefer: Option<AddressPreference>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             timeout: Duration::from_secs(DEFAULT_TIMEOUT),
+            connect_timeout: Duration::from_secs(DEFAULT_CONNECT_TIMEOUT),
+            prefer: None,
         }
     }
 }
 
-fn send_hl7_message_with_config(host: &str, port: u16, message: &str, config: Config) -> io::Result<String> {
-    let mut stream = TcpStream::connect((host, port))?;
-    
-    // Set timeouts
-    stream.set_read_timeout(Some(config.timeout))?;
-    stream.set_write_timeout(Some(config.timeout))?;
-    
-    // Prepare message with MLLP frame
-    let framed_message = format!("\x0B{}\x1C\x0D", message);
-    
-    // Write message
-    stream.write_all(framed_message.as_bytes())?;
-    stream.flush()?;
-    
-    // Read response with larger buffer
-    let mut buffer = Vec::new();
-    let mut temp_buffer = [0; BUFFER_SIZE];
-    
-    loop {
-        match stream.read(&mut temp_buffer) {
-            Ok(0) => break,
-            Ok(n) => {
-                buffer.extend_from_slice(&temp_buffer[..n]);
-                if buffer.ends_with(b"\x1C\x0D") {
-                    break;
-                }
-            },
-            Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
-            Err(e) => return Err(e),
-        }
-    }
-    
-    if buffer.is_empty() {
-        return Err(io::Error::new(io::ErrorKind::TimedOut, "Read timed out"));
-    }
-    
-    // Remove MLLP frame
-    let response = buffer.strip_prefix(b"\x0B").unwrap_or(&buffer);
-    let response = response.strip_suffix(b"\x1C\x0D").unwrap_or(response);
-    
-    String::from_utf8(response.to_vec())
-        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+fn send_hl7_message_with_config(host: &str, port: u16, message: &str, config: Config) -> Result<String, SendError> {
+    let mut stream =
+        connect_with_failover(host, port, config.prefer, config.connect_timeout).map_err(|source| {
+            SendError::Connect {
+                host: host.to_string(),
+                port,
+                source,
+            }
+        })?;
+
+    stream.set_read_timeout(Some(config.timeout)).map_err(SendError::Io)?;
+    stream.set_write_timeout(Some(config.timeout)).map_err(SendError::Io)?;
+
+    send_and_read_ack(&mut stream, message)
+}
+
+/// Frames `message` as MLLP, writes it to `stream`, and reads back exactly one ACK frame.
+fn send_and_read_ack<S: Read + Write>(stream: &mut S, message: &str) -> Result<String, SendError> {
+    stream.write_all(&frame_message(message)).map_err(SendError::Write)?;
+    stream.flush().map_err(SendError::Write)?;
+
+    let response = read_mllp_frame(stream, BUFFER_SIZE).map_err(classify_read_error)?;
+    String::from_utf8(response).map_err(SendError::Encoding)
+}
+
+/// Result of sending a single message within a batch run.
+struct BatchItemResult {
+    index: usize,
+    result: Result<String, SendError>,
+}
+
+/// Sends every message in `messages` over a single kept-alive `TcpStream`, reading one ACK
+/// per message, and reports the outcome of each send in order.
+fn send_batch_with_config(
+    host: &str,
+    port: u16,
+    messages: &[String],
+    config: Config,
+) -> Result<Vec<BatchItemResult>, SendError> {
+    let mut stream =
+        connect_with_failover(host, port, config.prefer, config.connect_timeout).map_err(|source| {
+            SendError::Connect {
+                host: host.to_string(),
+                port,
+                source,
+            }
+        })?;
+    stream.set_read_timeout(Some(config.timeout)).map_err(SendError::Io)?;
+    stream.set_write_timeout(Some(config.timeout)).map_err(SendError::Io)?;
+
+    Ok(messages
+        .iter()
+        .enumerate()
+        .map(|(index, message)| BatchItemResult {
+            index,
+            result: send_and_read_ack(&mut stream, message),
+        })
+        .collect())
+}
+
+/// Unix domain socket counterpart of [`send_hl7_message_with_config`], reusing the same
+/// MLLP framing and ACK-reading logic over a `UnixStream` instead of a `TcpStream`.
+#[cfg(unix)]
+fn send_hl7_message_via_socket(path: &str, message: &str, config: Config) -> Result<String, SendError> {
+    let mut stream = connect_unix_stream(path).map_err(SendError::Io)?;
+    stream.set_read_timeout(Some(config.timeout)).map_err(SendError::Io)?;
+    stream.set_write_timeout(Some(config.timeout)).map_err(SendError::Io)?;
+
+    send_and_read_ack(&mut stream, message)
+}
+
+/// `--socket` has no meaning off Unix; report that clearly rather than failing to compile
+/// or silently falling back to TCP.
+#[cfg(not(unix))]
+fn send_hl7_message_via_socket(_path: &str, _message: &str, _config: Config) -> Result<String, SendError> {
+    Err(SendError::Io(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "--socket is only supported on Unix targets",
+    )))
+}
+
+/// Unix domain socket counterpart of [`send_batch_with_config`].
+#[cfg(unix)]
+fn send_batch_via_socket(
+    path: &str,
+    messages: &[String],
+    config: Config,
+) -> Result<Vec<BatchItemResult>, SendError> {
+    let mut stream = connect_unix_stream(path).map_err(SendError::Io)?;
+    stream.set_read_timeout(Some(config.timeout)).map_err(SendError::Io)?;
+    stream.set_write_timeout(Some(config.timeout)).map_err(SendError::Io)?;
+
+    Ok(messages
+        .iter()
+        .enumerate()
+        .map(|(index, message)| BatchItemResult {
+            index,
+            result: send_and_read_ack(&mut stream, message),
+        })
+        .collect())
+}
+
+/// `--socket` has no meaning off Unix; see [`send_hl7_message_via_socket`].
+#[cfg(not(unix))]
+fn send_batch_via_socket(
+    _path: &str,
+    _messages: &[String],
+    _config: Config,
+) -> Result<Vec<BatchItemResult>, SendError> {
+    Err(SendError::Io(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "--socket is only supported on Unix targets",
+    )))
 }
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Args {
     /// Host address of the HL7 server
-    #[arg(short, long, default_value = "localhost")]
+    #[arg(long, default_value = "localhost")]
     host: String,
 
-    /// Port number of the HL7 server
-    #[arg(short, long)]
-    port: u16,
+    /// Port number of the HL7 server (required unless --socket is used)
+    #[arg(short, long, required_unless_present = "socket")]
+    port: Option<u16>,
 
-    /// Path to the HL7 message file
-    #[arg(short, long)]
-    message: String,
+    /// Path to the HL7 message file (required unless --bulk is used)
+    #[arg(short, long, required_unless_present = "bulk")]
+    message: Option<String>,
 
     /// Timeout in seconds
     #[arg(short, long, default_value = "30")]
     timeout: u64,
+
+    /// Per-address connect timeout in seconds, tried across every resolved address before
+    /// giving up
+    #[arg(long, default_value = "10")]
+    connect_timeout: u64,
+
+    /// Prefer IPv4 or IPv6 addresses when a host resolves to both; unset tries addresses
+    /// in the order the resolver returned them
+    #[arg(long, value_enum)]
+    prefer: Option<AddressPreference>,
+
+    /// Treat the message file as a batch of several HL7 messages sent over one connection
+    #[arg(long)]
+    batch: bool,
+
+    /// Delimiter separating messages in batch mode, ignored if the file is already MLLP-framed
+    #[arg(long, default_value = "\n\n")]
+    delimiter: String,
+
+    /// Treat a missing or unparseable acknowledgment as a hard failure
+    #[arg(long)]
+    expect_ack: bool,
+
+    /// Connect via a Unix domain socket instead of TCP (Unix targets only). A path
+    /// prefixed with "\0" (e.g. "\0hl7.sock") is connected as a Linux abstract socket.
+    /// Mutually exclusive with --host/--port.
+    #[arg(long, conflicts_with_all = ["host", "port"])]
+    socket: Option<String>,
+
+    /// Send every message found under the given directories and/or files concurrently,
+    /// using up to --concurrency kept-alive connections, and print a summary instead of a
+    /// per-message response. Requires the crate to be built with the `async` feature.
+    #[arg(long, num_args = 1.., value_name = "PATH", conflicts_with_all = ["message", "batch", "socket"])]
+    bulk: Option<Vec<PathBuf>>,
+
+    /// Number of concurrent connections to use for --bulk mode
+    #[arg(long, default_value = "4")]
+    concurrency: usize,
 }
 
-fn run(args: Args) -> Result<(), String> {
+/// Interprets the MSA acknowledgment in `response`, returning a summary to print on
+/// success (including a mere warning when none is found and it isn't required) or a
+/// `SendError::Nak` describing why the message should be considered unsuccessful.
+fn check_acknowledgment(response: &str, expect_ack: bool) -> Result<String, SendError> {
+    match ack::parse_acknowledgment(response) {
+        Some(acknowledgment) => {
+            let mut summary = format!("Acknowledgment code: {}", acknowledgment.raw_code);
+            if let Some(text) = &acknowledgment.text_message {
+                summary.push_str(&format!("\nMessage: {}", text));
+            }
+            if acknowledgment.is_accepted() {
+                Ok(summary)
+            } else {
+                Err(SendError::Nak(acknowledgment.raw_code))
+            }
+        }
+        None if expect_ack => Err(SendError::Nak("NONE".to_string())),
+        None => Ok("Warning: no parseable MSA acknowledgment found in response".to_string()),
+    }
+}
+
+fn run(args: Args) -> Result<(), AppError> {
+    if let Some(paths) = &args.bulk {
+        return run_bulk(&args, paths);
+    }
+
     let config = Config {
         timeout: Duration::from_secs(args.timeout),
+        connect_timeout: Duration::from_secs(args.connect_timeout),
+        prefer: args.prefer,
     };
 
-    let mut file = File::open(&args.message)
-        .map_err(|e| format!("Failed to open message file: {}", e))?;
+    let message_path = args
+        .message
+        .as_deref()
+        .expect("clap enforces --message unless --bulk is set");
+    let mut file = File::open(message_path).map_err(AppError::OpenMessageFile)?;
     let mut message = String::new();
     file.read_to_string(&mut message)
-        .map_err(|e| format!("Failed to read message file: {}", e))?;
-
-    match send_hl7_message_with_config(&args.host, args.port, &message, config) {
-        Ok(response) => {
-            println!("HL7 Message Sent");
-            println!("Response from server:");
-            println!("{}", response);
-            Ok(())
+        .map_err(AppError::ReadMessageFile)?;
+
+    if args.batch {
+        return run_batch(&args, &message, config);
+    }
+
+    let response = match &args.socket {
+        Some(path) => send_hl7_message_via_socket(path, &message, config)?,
+        None => {
+            let port = args.port.expect("clap enforces --port unless --socket is set");
+            send_hl7_message_with_config(&args.host, port, &message, config)?
         }
-        Err(e) => Err(format!("Failed to send HL7 message: {}", e)),
+    };
+    println!("HL7 Message Sent");
+    println!("Response from server:");
+    println!("{}", response);
+    let summary = check_acknowledgment(&response, args.expect_ack)?;
+    println!("{}", summary);
+    Ok(())
+}
+
+/// Reads every message under `paths` and hands them to the async, connection-pooled
+/// sender in [`bulk`], printing an aggregate summary. Returns `AppError::BatchFailures`
+/// if any message was rejected, timed out, hit a connection error, or was never delivered
+/// at all (e.g. every worker failed to connect), matching how `run_batch` reports failure.
+#[cfg(feature = "async")]
+fn run_bulk(args: &Args, paths: &[PathBuf]) -> Result<(), AppError> {
+    let files = bulk::collect_message_files(paths).map_err(AppError::Io)?;
+    let messages = files
+        .iter()
+        .map(std::fs::read_to_string)
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(AppError::ReadMessageFile)?;
+
+    if messages.is_empty() {
+        return Err(AppError::EmptyBatch);
+    }
+
+    let total = messages.len();
+    let port = args.port.expect("clap enforces --port unless --socket is set");
+    let runtime = tokio::runtime::Runtime::new().map_err(AppError::Io)?;
+    let summary = runtime.block_on(bulk::send_bulk(
+        args.host.clone(),
+        port,
+        messages,
+        args.concurrency,
+        Duration::from_secs(args.timeout),
+        Duration::from_secs(args.connect_timeout),
+        args.prefer,
+    ));
+
+    println!(
+        "Bulk send complete: {} sent, {} accepted, {} rejected, {} timed out, {} connection errors, {} undelivered in {:.2}s ({:.1} msg/s)",
+        summary.sent,
+        summary.accepted,
+        summary.rejected,
+        summary.timed_out,
+        summary.connection_errors,
+        summary.undelivered,
+        summary.elapsed.as_secs_f64(),
+        summary.messages_per_sec()
+    );
+
+    // Count messages, not workers: `connection_errors` also includes workers whose initial
+    // connect failed, so derive the failures from the messages that weren't accepted.
+    let failures = total.saturating_sub(summary.accepted);
+    if failures > 0 {
+        Err(AppError::BatchFailures(failures, total))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "async"))]
+fn run_bulk(_args: &Args, _paths: &[PathBuf]) -> Result<(), AppError> {
+    Err(AppError::AsyncFeatureDisabled)
+}
+
+fn run_batch(args: &Args, file_contents: &str, config: Config) -> Result<(), AppError> {
+    let messages = split_batch_input(file_contents, &args.delimiter);
+    if messages.is_empty() {
+        return Err(AppError::EmptyBatch);
+    }
+
+    let results = match &args.socket {
+        Some(path) => send_batch_via_socket(path, &messages, config)?,
+        None => {
+            let port = args.port.expect("clap enforces --port unless --socket is set");
+            send_batch_with_config(&args.host, port, &messages, config)?
+        }
+    };
+
+    let mut failures = 0;
+    for item in &results {
+        match &item.result {
+            Ok(response) => match check_acknowledgment(response, args.expect_ack) {
+                Ok(summary) => println!("[{}] {}", item.index + 1, summary),
+                Err(e) => {
+                    failures += 1;
+                    println!("[{}] NAK: {}", item.index + 1, e);
+                }
+            },
+            Err(e) => {
+                failures += 1;
+                println!("[{}] FAILED: {}", item.index + 1, e);
+            }
+        }
+    }
+
+    println!("Batch complete: {} sent, {} failed", results.len(), failures);
+
+    if failures > 0 {
+        Err(AppError::BatchFailures(failures, results.len()))
+    } else {
+        Ok(())
     }
 }
 
@@ -111,6 +375,6 @@ fn main() {
     let args = Args::parse();
     if let Err(e) = run(args) {
         eprintln!("{}", e);
-        std::process::exit(1);
+        std::process::exit(e.exit_code());
     }
 }
\ No newline at end of file
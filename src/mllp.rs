@@ -0,0 +1,233 @@
+//! MLLP (Minimal Lower Layer Protocol) framing.
+//!
+//! HL7 messages sent over TCP (or any other `Read`/`Write` transport) are wrapped in an
+//! MLLP envelope: a start block (`0x0B`), the message payload, then an end block
+//! (`0x1C`) followed by a carriage return (`0x0D`). This module implements a small state
+//! machine for both directions so the rest of the crate never has to reason about raw
+//! bytes on the wire.
+
+use std::io::{self, Read};
+
+pub const START_BLOCK: u8 = 0x0B;
+pub const END_BLOCK: u8 = 0x1C;
+pub const CARRIAGE_RETURN: u8 = 0x0D;
+
+/// Wraps `message` in an MLLP frame (`<VT>message<FS><CR>`).
+pub fn frame_message(message: &str) -> Vec<u8> {
+    let mut framed = Vec::with_capacity(message.len() + 3);
+    framed.push(START_BLOCK);
+    framed.extend_from_slice(message.as_bytes());
+    framed.push(END_BLOCK);
+    framed.push(CARRIAGE_RETURN);
+    framed
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FrameState {
+    AwaitingStart,
+    InPayload,
+    SawEndBlock,
+}
+
+/// Advances the MLLP framing state machine by one byte, pushing payload bytes onto
+/// `payload` as they're recognized. Returns `true` once `payload` holds a complete
+/// de-framed message. Shared by the sync and async frame readers so the two can never
+/// drift apart on edge cases like a terminator split across reads.
+fn step_frame(state: &mut FrameState, payload: &mut Vec<u8>, byte: u8) -> bool {
+    match *state {
+        FrameState::AwaitingStart => {
+            if byte == START_BLOCK {
+                *state = FrameState::InPayload;
+            }
+            false
+        }
+        FrameState::InPayload => {
+            if byte == END_BLOCK {
+                *state = FrameState::SawEndBlock;
+            } else {
+                payload.push(byte);
+            }
+            false
+        }
+        FrameState::SawEndBlock => {
+            if byte == CARRIAGE_RETURN {
+                return true;
+            }
+            // The 0x1C wasn't followed by 0x0D, so it was payload data after all.
+            payload.push(END_BLOCK);
+            if byte == END_BLOCK {
+                *state = FrameState::SawEndBlock;
+            } else {
+                payload.push(byte);
+                *state = FrameState::InPayload;
+            }
+            false
+        }
+    }
+}
+
+/// Reads exactly one MLLP-framed message from `reader`.
+///
+/// Bytes received before the start block are discarded, and the two-byte terminator is
+/// tracked explicitly so a `0x1C`/`0x0D` boundary split across two `read` calls is still
+/// recognized correctly. Returns the de-framed payload bytes.
+pub fn read_mllp_frame<R: Read>(reader: &mut R, read_buf_size: usize) -> io::Result<Vec<u8>> {
+    let mut state = FrameState::AwaitingStart;
+    let mut payload = Vec::new();
+    let mut temp_buffer = vec![0u8; read_buf_size];
+
+    loop {
+        let n = reader.read(&mut temp_buffer)?;
+        if n == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "connection closed before a complete MLLP frame was received",
+            ));
+        }
+
+        for &byte in &temp_buffer[..n] {
+            if step_frame(&mut state, &mut payload, byte) {
+                return Ok(payload);
+            }
+        }
+    }
+}
+
+/// Async counterpart of [`read_mllp_frame`] for the Tokio-based bulk sender, built on the
+/// same [`step_frame`] state machine so framing behavior is identical between the sync and
+/// async paths.
+#[cfg(feature = "async")]
+pub async fn read_mllp_frame_async<R>(reader: &mut R, read_buf_size: usize) -> io::Result<Vec<u8>>
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    use tokio::io::AsyncReadExt;
+
+    let mut state = FrameState::AwaitingStart;
+    let mut payload = Vec::new();
+    let mut temp_buffer = vec![0u8; read_buf_size];
+
+    loop {
+        let n = reader.read(&mut temp_buffer).await?;
+        if n == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "connection closed before a complete MLLP frame was received",
+            ));
+        }
+
+        for &byte in &temp_buffer[..n] {
+            if step_frame(&mut state, &mut payload, byte) {
+                return Ok(payload);
+            }
+        }
+    }
+}
+
+/// Splits the contents of a batch input file into individual, unframed HL7 messages.
+///
+/// If `content` already contains MLLP start blocks, each frame is extracted with the same
+/// state machine `read_mllp_frame` uses. Otherwise `content` is split on `delimiter`, with
+/// empty segments dropped.
+pub fn split_batch_input(content: &str, delimiter: &str) -> Vec<String> {
+    if content.as_bytes().contains(&START_BLOCK) {
+        split_framed_messages(content)
+    } else {
+        content
+            .split(delimiter)
+            .map(str::trim)
+            .filter(|segment| !segment.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+fn split_framed_messages(content: &str) -> Vec<String> {
+    let mut messages = Vec::new();
+    let mut state = FrameState::AwaitingStart;
+    let mut payload = Vec::new();
+
+    for &byte in content.as_bytes() {
+        if step_frame(&mut state, &mut payload, byte) {
+            if let Ok(message) = String::from_utf8(std::mem::take(&mut payload)) {
+                messages.push(message);
+            }
+            state = FrameState::AwaitingStart;
+        }
+    }
+
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn frame_message_wraps_in_start_and_end_blocks() {
+        let framed = frame_message("MSH|^~\\&|A");
+        assert_eq!(framed[0], START_BLOCK);
+        assert_eq!(&framed[framed.len() - 2..], &[END_BLOCK, CARRIAGE_RETURN]);
+    }
+
+    #[test]
+    fn read_mllp_frame_discards_leading_garbage() {
+        let mut input = Cursor::new(b"garbage\x0Bhello\x1C\x0D".to_vec());
+        let payload = read_mllp_frame(&mut input, 4096).unwrap();
+        assert_eq!(payload, b"hello");
+    }
+
+    #[test]
+    fn read_mllp_frame_handles_terminator_split_across_reads() {
+        struct SplitReader {
+            chunks: Vec<&'static [u8]>,
+        }
+
+        impl Read for SplitReader {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                if self.chunks.is_empty() {
+                    return Ok(0);
+                }
+                let chunk = self.chunks.remove(0);
+                buf[..chunk.len()].copy_from_slice(chunk);
+                Ok(chunk.len())
+            }
+        }
+
+        let mut reader = SplitReader {
+            chunks: vec![b"\x0Bhello\x1C", b"\x0D"],
+        };
+        let payload = read_mllp_frame(&mut reader, 4096).unwrap();
+        assert_eq!(payload, b"hello");
+    }
+
+    #[test]
+    fn read_mllp_frame_keeps_end_block_that_is_not_followed_by_cr() {
+        let mut input = Cursor::new(b"\x0Bhe\x1Cllo\x1C\x0D".to_vec());
+        let payload = read_mllp_frame(&mut input, 4096).unwrap();
+        assert_eq!(payload, b"he\x1Cllo");
+    }
+
+    #[test]
+    fn split_batch_input_parses_multiple_framed_messages() {
+        let content = "\x0Bfirst\x1C\x0D\x0Bsecond\x1C\x0D";
+        let messages = split_batch_input(content, "\n\n");
+        assert_eq!(messages, vec!["first".to_string(), "second".to_string()]);
+    }
+
+    #[test]
+    fn split_batch_input_splits_plain_messages_on_delimiter() {
+        let content = "first\n\nsecond\n\n";
+        let messages = split_batch_input(content, "\n\n");
+        assert_eq!(messages, vec!["first".to_string(), "second".to_string()]);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn read_mllp_frame_async_matches_the_sync_reader() {
+        let mut input = Cursor::new(b"garbage\x0Bhe\x1Cllo\x1C\x0D".to_vec());
+        let payload = read_mllp_frame_async(&mut input, 4096).await.unwrap();
+        assert_eq!(payload, b"he\x1Cllo");
+    }
+}
@@ -0,0 +1,114 @@
+//! Resolving a host to every candidate address and failing over between them.
+//!
+//! `TcpStream::connect((host, port))` only tries the first address the resolver returns
+//! and gives up immediately if that one is unreachable, which is fragile for dual-stack
+//! hosts. This module resolves the host to every candidate `SocketAddr` (covering both
+//! IPv4 and IPv6), optionally reorders them to prefer one address family, and attempts
+//! each address in turn with a per-attempt connect timeout until one succeeds.
+
+use std::io;
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+/// Controls which address family is tried first when a host resolves to both.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum AddressPreference {
+    Ipv4,
+    Ipv6,
+}
+
+/// Resolves `host:port` to every candidate address, orders them per `prefer` (stable
+/// within each family, so the resolver's own ordering is otherwise preserved), and
+/// connects to the first one that succeeds within `connect_timeout`. Returns the last
+/// connection error if every address fails.
+pub fn connect_with_failover(
+    host: &str,
+    port: u16,
+    prefer: Option<AddressPreference>,
+    connect_timeout: Duration,
+) -> io::Result<TcpStream> {
+    let addrs = resolve_ordered(host, port, prefer)?;
+    connect_to_first(&addrs, connect_timeout)
+}
+
+/// Resolves and orders candidate addresses without connecting. Exposed crate-wide so the
+/// async bulk sender in [`crate::bulk`] can fail over across addresses the same way this
+/// module's synchronous `connect_with_failover` does.
+pub(crate) fn resolve_ordered(host: &str, port: u16, prefer: Option<AddressPreference>) -> io::Result<Vec<SocketAddr>> {
+    let mut addrs: Vec<SocketAddr> = (host, port).to_socket_addrs()?.collect();
+    if addrs.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{host} did not resolve to any address"),
+        ));
+    }
+
+    if let Some(prefer) = prefer {
+        addrs.sort_by_key(|addr| address_rank(addr, prefer));
+    }
+
+    Ok(addrs)
+}
+
+/// Ranks `addr` for sorting: `0` if it matches `prefer`'s family, `1` otherwise. A stable
+/// sort on this key groups the preferred family first without disturbing relative order
+/// within either group.
+fn address_rank(addr: &SocketAddr, prefer: AddressPreference) -> u8 {
+    match (addr, prefer) {
+        (SocketAddr::V4(_), AddressPreference::Ipv4) | (SocketAddr::V6(_), AddressPreference::Ipv6) => 0,
+        _ => 1,
+    }
+}
+
+fn connect_to_first(addrs: &[SocketAddr], connect_timeout: Duration) -> io::Result<TcpStream> {
+    let mut last_error = None;
+    for addr in addrs {
+        match TcpStream::connect_timeout(addr, connect_timeout) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(last_error.expect("addrs is non-empty, so the loop above ran at least once"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{Ipv4Addr, Ipv6Addr, TcpListener};
+
+    fn v4(port: u16) -> SocketAddr {
+        SocketAddr::new(Ipv4Addr::LOCALHOST.into(), port)
+    }
+
+    fn v6(port: u16) -> SocketAddr {
+        SocketAddr::new(Ipv6Addr::LOCALHOST.into(), port)
+    }
+
+    #[test]
+    fn address_rank_puts_the_preferred_family_first() {
+        let mut addrs = [v6(1), v4(1)];
+        addrs.sort_by_key(|addr| address_rank(addr, AddressPreference::Ipv4));
+        assert!(matches!(addrs[0], SocketAddr::V4(_)));
+
+        let mut addrs = [v4(1), v6(1)];
+        addrs.sort_by_key(|addr| address_rank(addr, AddressPreference::Ipv6));
+        assert!(matches!(addrs[0], SocketAddr::V6(_)));
+    }
+
+    #[test]
+    fn connect_to_first_skips_unreachable_addresses_and_falls_back() {
+        let listener = TcpListener::bind(v4(0)).unwrap();
+        let good_addr = listener.local_addr().unwrap();
+        // Port 1 is reserved and nothing should be listening on it in a test sandbox.
+        let dead_addr = v4(1);
+
+        let stream = connect_to_first(&[dead_addr, good_addr], Duration::from_millis(500));
+        assert!(stream.is_ok());
+    }
+
+    #[test]
+    fn connect_to_first_returns_the_last_error_when_every_address_fails() {
+        let result = connect_to_first(&[v4(1), v4(2)], Duration::from_millis(200));
+        assert!(result.is_err());
+    }
+}
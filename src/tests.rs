@@ -24,13 +24,13 @@ impl MockTcpServer {
         self.listener.local_addr().unwrap().port()
     }
 
-    /// Handles an incoming connection by sending a predefined response
+    /// Handles an incoming connection by sending a predefined, MLLP-framed response
     fn handle_connection(mut stream: TcpStream, response: &str) {
         let mut buffer = [0; 1024];
         // Read incoming data (simulated)
         let _ = stream.read(&mut buffer);
         // Write the response back to the client
-        stream.write_all(response.as_bytes()).unwrap();
+        stream.write_all(&crate::mllp::frame_message(response)).unwrap();
     }
 }
 
@@ -62,15 +62,23 @@ fn test_send_hl7_message_success() {
 
     let args = Args {
         host: "localhost".to_string(),
-        port,
-        message: "test.hl7".to_string(),
+        port: Some(port),
+        message: Some("test.hl7".to_string()),
         timeout: 30,
+        connect_timeout: 10,
+        prefer: None,
+        batch: false,
+        delimiter: "\n\n".to_string(),
+        expect_ack: false,
+        socket: None,
+        bulk: None,
+        concurrency: 4,
     };
 
     let message = "MSH|^~\\&|SendingApp|SendingFac|ReceivingApp|SendingFac|202401011230||MDM^T02|MSG123|P|2.5|||||ASCII\r\
                   OBX|1|ED|PDF^Application^PDF^Base64||dGVzdCBwZGY=|||||F\r\x1c\r";
 
-    let result = send_hl7_message_with_config(&args.host, args.port, message, Config::default());
+    let result = send_hl7_message_with_config(&args.host, args.port.unwrap(), message, Config::default());
     assert!(result.is_ok());
     assert!(result.unwrap().contains("ACK"));
 }
@@ -98,12 +106,10 @@ fn test_send_hl7_message_would_block() {
                   OBX|1|ED|PDF^Application^PDF^Base64||dGVzdCBwZGY=|||||F\r\x1c\r";
     let result = send_hl7_message_with_config("127.0.0.1", port, message, Config::default());
     assert!(result.is_err());
-    let kind = result.unwrap_err().kind();
-    assert!(
-        kind == io::ErrorKind::TimedOut
-            || kind == io::ErrorKind::WouldBlock
-            || kind == io::ErrorKind::ConnectionReset
-    );
+    assert!(matches!(
+        result.unwrap_err(),
+        SendError::Timeout(_) | SendError::Io(_) | SendError::IncompleteFrame
+    ));
 }
 
 #[test]
@@ -113,9 +119,12 @@ fn test_send_hl7_message_invalid_utf8() {
 
     thread::spawn(move || {
         if let Ok((mut stream, _)) = server.listener.accept() {
-            // Send invalid UTF-8 bytes
-            let invalid_bytes = vec![0xff, 0xfe, 0xfd];
-            stream.write_all(&invalid_bytes).unwrap();
+            // Send an MLLP-framed response carrying invalid UTF-8 bytes
+            let invalid_bytes: [u8; 3] = [0xff, 0xfe, 0xfd];
+            let mut framed = vec![crate::mllp::START_BLOCK];
+            framed.extend_from_slice(&invalid_bytes);
+            framed.extend_from_slice(&[crate::mllp::END_BLOCK, crate::mllp::CARRIAGE_RETURN]);
+            stream.write_all(&framed).unwrap();
         }
     });
 
@@ -123,11 +132,10 @@ fn test_send_hl7_message_invalid_utf8() {
                   OBX|1|ED|PDF^Application^PDF^Base64||dGVzdCBwZGY=|||||F\r\x1c\r";
     let result = send_hl7_message_with_config("127.0.0.1", port, message, Config::default());
     assert!(result.is_err());
-    let kind = result.unwrap_err().kind();
-    assert!(
-        kind == io::ErrorKind::InvalidData
-            || kind == io::ErrorKind::ConnectionReset
-    );
+    assert!(matches!(
+        result.unwrap_err(),
+        SendError::Encoding(_) | SendError::Io(_) | SendError::IncompleteFrame
+    ));
 }
 
 #[test]
@@ -145,37 +153,52 @@ fn test_send_hl7_message_timeout() {
                   OBX|1|ED|PDF^Application^PDF^Base64||dGVzdCBwZGY=|||||F\r\x1c\r";
     let result = send_hl7_message_with_config("127.0.0.1", port, message, Config::default());
     assert!(result.is_err());
-    let kind = result.unwrap_err().kind();
-    assert!(
-        kind == io::ErrorKind::TimedOut
-            || kind == io::ErrorKind::ConnectionReset
-    );
+    assert!(matches!(
+        result.unwrap_err(),
+        SendError::Timeout(_) | SendError::Io(_) | SendError::IncompleteFrame
+    ));
 }
 
 #[test]
 fn test_run_invalid_arguments() {
     let args = Args {
         host: "".to_string(),
-        port: 0,
-        message: "".to_string(),
+        port: Some(0),
+        message: Some("".to_string()),
         timeout: 0,
+        connect_timeout: 10,
+        prefer: None,
+        batch: false,
+        delimiter: "\n\n".to_string(),
+        expect_ack: false,
+        socket: None,
+        bulk: None,
+        concurrency: 4,
     };
     let result = run(args);
     assert!(result.is_err());
-    assert!(result.unwrap_err().contains("Failed to open message file"));
+    assert!(matches!(result.unwrap_err(), AppError::OpenMessageFile(_)));
 }
 
 #[test]
 fn test_run_send_hl7_failure() {
     let args = Args {
         host: "localhost".to_string(),
-        port: 9999,
-        message: "/nonexistent/path/message.hl7".to_string(),
+        port: Some(9999),
+        message: Some("/nonexistent/path/message.hl7".to_string()),
         timeout: 30,
+        connect_timeout: 10,
+        prefer: None,
+        batch: false,
+        delimiter: "\n\n".to_string(),
+        expect_ack: false,
+        socket: None,
+        bulk: None,
+        concurrency: 4,
     };
     let result = run(args);
     assert!(result.is_err());
-    assert!(result.unwrap_err().contains("Failed to open message file"));
+    assert!(matches!(result.unwrap_err(), AppError::OpenMessageFile(_)));
 }
 
 #[test]
@@ -195,9 +218,17 @@ fn test_run_send_hl7_success() {
 
     let args = Args {
         host: "localhost".to_string(),
-        port,
-        message: test_msg.path.to_str().unwrap().to_string(),
+        port: Some(port),
+        message: Some(test_msg.path.to_str().unwrap().to_string()),
         timeout: 30,
+        connect_timeout: 10,
+        prefer: None,
+        batch: false,
+        delimiter: "\n\n".to_string(),
+        expect_ack: false,
+        socket: None,
+        bulk: None,
+        concurrency: 4,
     };
     let result = run(args);
     assert!(result.is_ok());
@@ -222,8 +253,7 @@ fn test_send_hl7_message_would_block_mapping() {
                   OBX|1|ED|PDF^Application^PDF^Base64||dGVzdCBwZGY=|||||F\r\x1c\r";
     let result = send_hl7_message_with_config("127.0.0.1", port, message, Config::default());
     assert!(result.is_err());
-    let error = result.unwrap_err();
-    assert!(error.kind() == io::ErrorKind::TimedOut);
+    assert!(matches!(result.unwrap_err(), SendError::Timeout(_)));
 }
 
 #[test]
@@ -247,13 +277,15 @@ fn test_send_hl7_message_custom_timeout() {
                 let mut buffer = [0; 1024];
                 let _ = stream.read(&mut buffer);
                 thread::sleep(Duration::from_secs(1)); // Delay shorter than timeout
-                stream.write_all(b"ACK").unwrap();
+                stream.write_all(&crate::mllp::frame_message("ACK")).unwrap();
             }
         });
 
         // Test with long timeout - should succeed
         let config = Config {
             timeout: Duration::from_secs(LONG_TIMEOUT_SECS),
+            connect_timeout: Duration::from_secs(LONG_TIMEOUT_SECS),
+            prefer: None,
         };
         let result = send_hl7_message_with_config("127.0.0.1", port, TEST_MESSAGE, config);
         assert!(result.is_ok(), "Expected successful message delivery with {LONG_TIMEOUT_SECS}s timeout");
@@ -271,30 +303,30 @@ fn test_send_hl7_message_custom_timeout() {
                 let mut buffer = [0; 1024];
                 let _ = stream.read(&mut buffer);
                 thread::sleep(Duration::from_secs(SERVER_DELAY_SECS)); // Delay longer than timeout
-                let _ = stream.write_all(b"ACK"); // Write should not succeed due to timeout
+                let _ = stream.write_all(&crate::mllp::frame_message("ACK")); // Write should not succeed due to timeout
             }
         });
 
         // Test with short timeout - should fail with timeout
         let config = Config {
             timeout: Duration::from_millis(SHORT_TIMEOUT_MILLIS),
+            connect_timeout: Duration::from_secs(LONG_TIMEOUT_SECS),
+            prefer: None,
         };
         let result = send_hl7_message_with_config("127.0.0.1", port, TEST_MESSAGE, config);
         
         assert!(result.is_err(), "Expected timeout error with {}ms timeout", SHORT_TIMEOUT_MILLIS);
         let error = result.unwrap_err();
-        // Debug output, uncomment to see error kind
-        // eprintln!("Received error kind: {:?}", error.kind()); 
-        
+        // Debug output, uncomment to see the error variant
+        // eprintln!("Received error: {:?}", error);
+
         // Check for expected timeout-related errors
         assert!(
-            matches!(error.kind(),
-                io::ErrorKind::TimedOut |      // Standard timeout
-                io::ErrorKind::WouldBlock |    // Non-blocking operation would block
-                io::ErrorKind::ConnectionReset | // Connection reset by peer
-                io::ErrorKind::UnexpectedEof    // Connection closed unexpectedly
+            matches!(
+                error,
+                SendError::Timeout(_) | SendError::Io(_) | SendError::IncompleteFrame
             ),
-            "Expected timeout-related error, got: {:?}", error.kind()
+            "Expected timeout-related error, got: {:?}", error
         );
     }
 }
@@ -316,15 +348,318 @@ fn test_run_with_custom_timeout() {
 
     let args = Args {
         host: "localhost".to_string(),
-        port,
-        message: test_msg.path.to_str().unwrap().to_string(),
+        port: Some(port),
+        message: Some(test_msg.path.to_str().unwrap().to_string()),
         timeout: 45,
+        connect_timeout: 10,
+        prefer: None,
+        batch: false,
+        delimiter: "\n\n".to_string(),
+        expect_ack: false,
+        socket: None,
+        bulk: None,
+        concurrency: 4,
     };
 
     let config = Config {
         timeout: Duration::from_secs(args.timeout),
+        connect_timeout: Duration::from_secs(args.connect_timeout),
+        prefer: args.prefer,
     };
 
-    let result = send_hl7_message_with_config(&args.host, args.port, &message_content, config);
+    let result = send_hl7_message_with_config(&args.host, args.port.unwrap(), message_content, config);
     assert!(result.is_ok());
 }
+
+#[test]
+fn test_send_batch_with_config_sends_all_messages_on_one_connection() {
+    let server = MockTcpServer::new();
+    let port = server.port();
+
+    thread::spawn(move || {
+        if let Ok((mut stream, _)) = server.listener.accept() {
+            for _ in 0..3 {
+                let mut buffer = [0; 1024];
+                if stream.read(&mut buffer).unwrap_or(0) == 0 {
+                    break;
+                }
+                stream
+                    .write_all(&crate::mllp::frame_message("ACK"))
+                    .unwrap();
+            }
+        }
+    });
+
+    let messages = vec!["MSG1".to_string(), "MSG2".to_string(), "MSG3".to_string()];
+    let results = send_batch_with_config("127.0.0.1", port, &messages, Config::default()).unwrap();
+
+    assert_eq!(results.len(), 3);
+    for (i, item) in results.iter().enumerate() {
+        assert_eq!(item.index, i);
+        assert!(item.result.is_ok());
+        assert!(item.result.as_ref().unwrap().contains("ACK"));
+    }
+}
+
+#[test]
+fn test_run_batch_mode_reports_per_message_results() {
+    let server = MockTcpServer::new();
+    let port = server.port();
+
+    thread::spawn(move || {
+        if let Ok((mut stream, _)) = server.listener.accept() {
+            for _ in 0..2 {
+                let mut buffer = [0; 1024];
+                if stream.read(&mut buffer).unwrap_or(0) == 0 {
+                    break;
+                }
+                stream
+                    .write_all(&crate::mllp::frame_message("ACK"))
+                    .unwrap();
+            }
+        }
+    });
+
+    let batch_content = "MSG1\n\nMSG2\n\n";
+    let test_msg = create_test_message(batch_content).unwrap();
+
+    let args = Args {
+        host: "localhost".to_string(),
+        port: Some(port),
+        message: Some(test_msg.path.to_str().unwrap().to_string()),
+        timeout: 30,
+        connect_timeout: 10,
+        prefer: None,
+        batch: true,
+        delimiter: "\n\n".to_string(),
+        expect_ack: false,
+        socket: None,
+        bulk: None,
+        concurrency: 4,
+    };
+    let result = run(args);
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_run_exits_with_error_on_negative_acknowledgment() {
+    let server = MockTcpServer::new();
+    let port = server.port();
+
+    thread::spawn(move || {
+        if let Ok((mut stream, _)) = server.listener.accept() {
+            let mut buffer = [0; 1024];
+            let _ = stream.read(&mut buffer);
+            let response = "MSH|^~\\&|Recv|Fac|Send|Fac|20240101||ACK|MSG1|P|2.5\rMSA|AR|MSG123|Unknown receiver";
+            stream
+                .write_all(&crate::mllp::frame_message(response))
+                .unwrap();
+        }
+    });
+
+    let message_content = "MSH|^~\\&|SendingApp|SendingFac|ReceivingApp|SendingFac|202401011230||MDM^T02|MSG123|P|2.5\r";
+    let test_msg = create_test_message(message_content).unwrap();
+
+    let args = Args {
+        host: "localhost".to_string(),
+        port: Some(port),
+        message: Some(test_msg.path.to_str().unwrap().to_string()),
+        timeout: 30,
+        connect_timeout: 10,
+        prefer: None,
+        batch: false,
+        delimiter: "\n\n".to_string(),
+        expect_ack: false,
+        socket: None,
+        bulk: None,
+        concurrency: 4,
+    };
+    let result = run(args);
+    assert!(matches!(
+        result,
+        Err(AppError::Send(SendError::Nak(code))) if code == "AR"
+    ));
+}
+
+#[test]
+fn test_run_with_expect_ack_fails_on_missing_acknowledgment() {
+    let server = MockTcpServer::new();
+    let port = server.port();
+
+    thread::spawn(move || {
+        if let Ok((stream, _)) = server.listener.accept() {
+            MockTcpServer::handle_connection(stream, "ACK");
+        }
+    });
+
+    let message_content = "MSH|^~\\&|SendingApp|SendingFac|ReceivingApp|SendingFac|202401011230||MDM^T02|MSG123|P|2.5\r";
+    let test_msg = create_test_message(message_content).unwrap();
+
+    let args = Args {
+        host: "localhost".to_string(),
+        port: Some(port),
+        message: Some(test_msg.path.to_str().unwrap().to_string()),
+        timeout: 30,
+        connect_timeout: 10,
+        prefer: None,
+        batch: false,
+        delimiter: "\n\n".to_string(),
+        expect_ack: true,
+        socket: None,
+        bulk: None,
+        concurrency: 4,
+    };
+    let result = run(args);
+    assert!(matches!(
+        result,
+        Err(AppError::Send(SendError::Nak(code))) if code == "NONE"
+    ));
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn test_run_bulk_mode_sends_every_file_in_a_directory() {
+    let server = MockTcpServer::new();
+    let port = server.port();
+
+    thread::spawn(move || {
+        while let Ok((mut stream, _)) = server.listener.accept() {
+            thread::spawn(move || loop {
+                let mut buffer = [0; 1024];
+                if stream.read(&mut buffer).unwrap_or(0) == 0 {
+                    return;
+                }
+                if stream
+                    .write_all(&crate::mllp::frame_message(
+                        "MSH|^~\\&|Recv|Fac|Send|Fac|20240101||ACK|MSG1|P|2.5\rMSA|AA|MSG1",
+                    ))
+                    .is_err()
+                {
+                    return;
+                }
+            });
+        }
+    });
+
+    let dir = tempdir().unwrap();
+    for i in 0..5 {
+        write(dir.path().join(format!("msg{i}.hl7")), format!("MSH|^~\\&|A|B|C|D|20240101||ORU|{i}|P|2.5")).unwrap();
+    }
+
+    let args = Args {
+        host: "127.0.0.1".to_string(),
+        port: Some(port),
+        message: None,
+        timeout: 5,
+        connect_timeout: 10,
+        prefer: None,
+        batch: false,
+        delimiter: "\n\n".to_string(),
+        expect_ack: false,
+        socket: None,
+        bulk: Some(vec![dir.path().to_path_buf()]),
+        concurrency: 2,
+    };
+    let result = run(args);
+    assert!(result.is_ok());
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn test_run_bulk_mode_fails_without_the_async_feature() {
+    let args = Args {
+        host: "127.0.0.1".to_string(),
+        port: Some(1),
+        message: None,
+        timeout: 5,
+        connect_timeout: 10,
+        prefer: None,
+        batch: false,
+        delimiter: "\n\n".to_string(),
+        expect_ack: false,
+        socket: None,
+        bulk: Some(vec![PathBuf::from("/nonexistent")]),
+        concurrency: 2,
+    };
+    let result = run(args);
+    assert!(matches!(result, Err(AppError::AsyncFeatureDisabled)));
+}
+
+#[test]
+#[cfg(unix)]
+fn test_run_sends_over_unix_socket() {
+    let dir = tempdir().unwrap();
+    let socket_path = dir.path().join("hl7.sock");
+    let listener = std::os::unix::net::UnixListener::bind(&socket_path).unwrap();
+
+    thread::spawn(move || {
+        if let Ok((mut stream, _)) = listener.accept() {
+            let mut buffer = [0; 1024];
+            let _ = stream.read(&mut buffer);
+            stream
+                .write_all(&crate::mllp::frame_message("ACK"))
+                .unwrap();
+        }
+    });
+
+    let message_content = "MSH|^~\\&|SendingApp|SendingFac|ReceivingApp|SendingFac|202401011230||MDM^T02|MSG123|P|2.5\r";
+    let test_msg = create_test_message(message_content).unwrap();
+
+    let args = Args {
+        host: "localhost".to_string(),
+        port: None,
+        message: Some(test_msg.path.to_str().unwrap().to_string()),
+        timeout: 30,
+        connect_timeout: 10,
+        prefer: None,
+        batch: false,
+        delimiter: "\n\n".to_string(),
+        expect_ack: false,
+        socket: Some(socket_path.to_str().unwrap().to_string()),
+        bulk: None,
+        concurrency: 4,
+    };
+    let result = run(args);
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_args_socket_conflicts_with_host_and_port() {
+    let result = Args::try_parse_from(["hl7sender", "--message", "msg.hl7", "--socket", "/tmp/hl7.sock", "--port", "2575"]);
+    assert!(result.is_err(), "expected --socket to conflict with --port");
+
+    let result = Args::try_parse_from(["hl7sender", "--message", "msg.hl7", "--socket", "/tmp/hl7.sock", "--host", "otherhost"]);
+    assert!(result.is_err(), "expected --socket to conflict with --host");
+}
+
+#[test]
+fn test_args_socket_alone_parses_without_port() {
+    let result = Args::try_parse_from(["hl7sender", "--message", "msg.hl7", "--socket", "/tmp/hl7.sock"]);
+    assert!(result.is_ok(), "--socket should satisfy the port requirement on its own");
+}
+
+#[test]
+fn test_args_bulk_conflicts_with_message_batch_and_socket() {
+    let base = ["hl7sender", "--port", "2575", "--bulk", "dir"];
+
+    let mut with_message = base.to_vec();
+    with_message.extend(["--message", "msg.hl7"]);
+    assert!(
+        Args::try_parse_from(with_message).is_err(),
+        "expected --bulk to conflict with --message"
+    );
+
+    let mut with_batch = base.to_vec();
+    with_batch.push("--batch");
+    assert!(Args::try_parse_from(with_batch).is_err(), "expected --bulk to conflict with --batch");
+
+    let mut with_socket = base.to_vec();
+    with_socket.extend(["--socket", "/tmp/hl7.sock"]);
+    assert!(Args::try_parse_from(with_socket).is_err(), "expected --bulk to conflict with --socket");
+}
+
+#[test]
+fn test_args_bulk_alone_parses_without_message() {
+    let result = Args::try_parse_from(["hl7sender", "--port", "2575", "--bulk", "dir"]);
+    assert!(result.is_ok(), "--bulk should satisfy the message requirement on its own");
+}
@@ -0,0 +1,103 @@
+//! Unix domain socket transport, including Linux abstract sockets.
+//!
+//! MLLP framing, timeout handling, and response parsing don't care what the underlying
+//! stream is (see [`crate::send_and_read_ack`]); this module only knows how to open a
+//! `UnixStream`, including decoding the escaped-null convention used by other tools to
+//! address an abstract socket (e.g. `\0hl7.sock`) instead of a path on disk.
+//!
+//! Only built for Unix targets (see `#[cfg(unix)]` on `mod transport` in `main.rs`).
+//! Abstract sockets are further restricted to Linux, since `std::os::linux::net` isn't
+//! available on other Unix targets (macOS, BSD, ...); a `\0`-prefixed path there returns a
+//! clear error instead of failing to compile.
+
+use std::io;
+use std::os::unix::net::{SocketAddr, UnixStream};
+
+/// Connects to a Unix domain socket at `path`. A path beginning with the two characters
+/// `\` and `0` (as typed on a command line, since a real NUL can't appear in an argument)
+/// is treated as a Linux abstract socket name and connected to accordingly; anything else
+/// is treated as an ordinary filesystem path.
+pub fn connect_unix_stream(path: &str) -> io::Result<UnixStream> {
+    let address = match path.strip_prefix("\\0") {
+        Some(name) => abstract_socket_address(name)?,
+        None => SocketAddr::from_pathname(path)?,
+    };
+    UnixStream::connect_addr(&address)
+}
+
+#[cfg(target_os = "linux")]
+fn abstract_socket_address(name: &str) -> io::Result<SocketAddr> {
+    use std::os::linux::net::SocketAddrExt;
+    SocketAddr::from_abstract_name(name.as_bytes())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn abstract_socket_address(_name: &str) -> io::Result<SocketAddr> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "abstract Unix sockets (a \\0-prefixed --socket path) are only supported on Linux",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::thread;
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn connect_unix_stream_reaches_an_abstract_socket() {
+        use std::os::linux::net::SocketAddrExt;
+
+        let name = format!("hl7sender-test-{:?}", thread::current().id());
+        let address = SocketAddr::from_abstract_name(name.as_bytes()).unwrap();
+        let listener = std::os::unix::net::UnixListener::bind_addr(&address).unwrap();
+
+        let handle = thread::spawn(move || {
+            if let Ok((mut stream, _)) = listener.accept() {
+                let mut buffer = [0; 16];
+                let n = stream.read(&mut buffer).unwrap();
+                stream.write_all(&buffer[..n]).unwrap();
+            }
+        });
+
+        let mut client = connect_unix_stream(&format!("\\0{name}")).unwrap();
+        client.write_all(b"ping").unwrap();
+        let mut response = [0; 4];
+        client.read_exact(&mut response).unwrap();
+        assert_eq!(&response, b"ping");
+
+        handle.join().unwrap();
+    }
+
+    #[test]
+    #[cfg(not(target_os = "linux"))]
+    fn connect_unix_stream_reports_abstract_sockets_as_unsupported() {
+        let result = connect_unix_stream("\\0hl7sender-test");
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::Unsupported);
+    }
+
+    #[test]
+    fn connect_unix_stream_reaches_a_filesystem_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hl7.sock");
+        let listener = std::os::unix::net::UnixListener::bind(&path).unwrap();
+
+        let handle = thread::spawn(move || {
+            if let Ok((mut stream, _)) = listener.accept() {
+                let mut buffer = [0; 16];
+                let n = stream.read(&mut buffer).unwrap();
+                stream.write_all(&buffer[..n]).unwrap();
+            }
+        });
+
+        let mut client = connect_unix_stream(path.to_str().unwrap()).unwrap();
+        client.write_all(b"ping").unwrap();
+        let mut response = [0; 4];
+        client.read_exact(&mut response).unwrap();
+        assert_eq!(&response, b"ping");
+
+        handle.join().unwrap();
+    }
+}